@@ -3,17 +3,179 @@ declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 #[program]
 pub mod counter {
     use super::*;
-    pub fn increment(ctx: Context<Increment>) -> Result<()> {
-        ctx.accounts.counter.count += 1;
+    pub fn initialize(ctx: Context<Initialize>, auth_program: Option<Pubkey>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.count = 0;
+        counter.authority = ctx.accounts.authority.key();
+        counter.bump = ctx.bumps.counter;
+        counter.auth_program = auth_program;
         Ok(())
     }
+
+    pub fn increment(ctx: Context<ModifyCounter>) -> Result<()> {
+        let current_count = ctx.accounts.counter.count;
+        let new_count = current_count.checked_add(1).ok_or(CounterError::Overflow)?;
+        apply_count(&ctx.accounts.counter, &ctx.accounts.auth_program, ctx.remaining_accounts, current_count, new_count)?;
+        ctx.accounts.counter.count = new_count;
+        emit_count_changed(&ctx.accounts.counter);
+        Ok(())
+    }
+
+    pub fn decrement(ctx: Context<ModifyCounter>) -> Result<()> {
+        let current_count = ctx.accounts.counter.count;
+        let new_count = current_count.checked_sub(1).ok_or(CounterError::Underflow)?;
+        apply_count(&ctx.accounts.counter, &ctx.accounts.auth_program, ctx.remaining_accounts, current_count, new_count)?;
+        ctx.accounts.counter.count = new_count;
+        emit_count_changed(&ctx.accounts.counter);
+        Ok(())
+    }
+
+    pub fn set_count(ctx: Context<ModifyCounter>, new_count: u64) -> Result<()> {
+        let current_count = ctx.accounts.counter.count;
+        apply_count(&ctx.accounts.counter, &ctx.accounts.auth_program, ctx.remaining_accounts, current_count, new_count)?;
+        ctx.accounts.counter.count = new_count;
+        emit_count_changed(&ctx.accounts.counter);
+        Ok(())
+    }
+}
+
+fn apply_count<'info>(
+    counter: &Account<'info, Counter>,
+    auth_program: &Option<UncheckedAccount<'info>>,
+    remaining_accounts: &[AccountInfo<'info>],
+    current_count: u64,
+    new_count: u64,
+) -> Result<()> {
+    match (auth_program, counter.auth_program) {
+        (Some(auth_program), Some(expected)) => {
+            require_keys_eq!(auth_program.key(), expected, CounterError::Unauthorized);
+            auth_interface::is_authorized(
+                CpiContext::new(
+                    auth_program.to_account_info(),
+                    auth_interface::IsAuthorized {
+                        program: auth_program.to_account_info(),
+                    },
+                )
+                .with_remaining_accounts(remaining_accounts.to_vec()),
+                current_count,
+                new_count,
+            )
+        }
+        (None, None) => Ok(()),
+        _ => err!(CounterError::Unauthorized),
+    }
+}
+
+fn emit_count_changed(counter: &Account<Counter>) {
+    emit!(CountChanged {
+        counter: counter.key(),
+        count: counter.count,
+        authority: counter.authority,
+    });
+}
+
+pub mod auth_interface {
+    use super::*;
+    use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+    use anchor_lang::solana_program::program::invoke;
+
+    pub fn is_authorized<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, IsAuthorized<'info>>,
+        current_count: u64,
+        new_count: u64,
+    ) -> Result<()> {
+        let mut data = sighash("is_authorized").to_vec();
+        data.extend_from_slice(&current_count.to_le_bytes());
+        data.extend_from_slice(&new_count.to_le_bytes());
+
+        let accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect();
+
+        let ix = Instruction {
+            program_id: *ctx.program.key,
+            accounts,
+            data,
+        };
+
+        let mut account_infos = ctx.remaining_accounts;
+        account_infos.push(ctx.program);
+        invoke(&ix, &account_infos)?;
+        Ok(())
+    }
+
+    fn sighash(name: &str) -> [u8; 8] {
+        let preimage = format!("global:{}", name);
+        let mut sighash = [0u8; 8];
+        sighash.copy_from_slice(&anchor_lang::solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8]);
+        sighash
+    }
+
+    #[derive(Accounts)]
+    pub struct IsAuthorized<'info> {
+        /// CHECK: target program is validated against `Counter::auth_program` by the caller
+        pub program: AccountInfo<'info>,
+    }
 }
 #[derive(Accounts)]
-pub struct Increment<'info> {
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = Counter::SIZE,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
     #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+pub struct ModifyCounter<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump = counter.bump
+    )]
     pub counter: Account<'info, Counter>,
+    pub authority: Signer<'info>,
+    /// CHECK: only read to build the `is_authorized` CPI; validated against `counter.auth_program`
+    pub auth_program: Option<UncheckedAccount<'info>>,
 }
 #[account]
 pub struct Counter {
     pub count: u64,
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub auth_program: Option<Pubkey>,
+}
+
+impl Counter {
+    pub const SIZE: usize = 8 + 32 + 8 + 1 + (1 + 32);
+}
+
+#[event]
+pub struct CountChanged {
+    #[index]
+    pub counter: Pubkey,
+    pub count: u64,
+    pub authority: Pubkey,
+}
+
+#[error_code]
+pub enum CounterError {
+    #[msg("the counter's auth_program was not satisfied")]
+    Unauthorized,
+    #[msg("count would overflow u64")]
+    Overflow,
+    #[msg("count would underflow below zero")]
+    Underflow,
 }
\ No newline at end of file